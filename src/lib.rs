@@ -14,14 +14,31 @@
 //! indicates whether the value is owned or not. This allows to use
 //! the value by accessing directly the pointer, without the overhead
 //! of matching an enum needed by `Cow`.
+//!
+//! The crate is `#![no_std]` and only needs `core` and `alloc`. The
+//! `std` feature (enabled by default) adds the conversions from
+//! `CString`, `OsString` and `PathBuf`, which are only available with
+//! `std`.
+
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+#[cfg(feature = "serde")]
+extern crate serde;
 
 #[cfg(test)]
 mod tests;
 
-use std::ptr::NonNull;
-use std::marker::PhantomData;
-use std::fmt::Debug;
-use std::hash::{Hash, Hasher};
+#[cfg(feature = "serde")]
+mod serde_impls;
+
+use core::ptr::NonNull;
+use core::marker::PhantomData;
+use core::fmt::Debug;
+use core::hash::{Hash, Hasher};
+use alloc::boxed::Box;
 
 /// The `Rob` type. See the crate documentation.
 pub struct Rob<'a, T: 'a + ?Sized> {
@@ -44,7 +61,7 @@ where
 impl<'a, T: 'a + ?Sized> Drop for Rob<'a, T> {
     fn drop(&mut self) {
         if self.is_owned {
-            unsafe { Box::from_raw(self.ptr.as_ptr()) };
+            let _ = unsafe { Box::from_raw(self.ptr.as_ptr()) };
         }
     }
 }
@@ -87,7 +104,7 @@ impl<'a, T: 'a + ?Sized> Rob<'a, T> {
             marker2: PhantomData,
         }
     }
-    
+
     /// Creates a new `Rob` with an owned value that is already boxed.
     ///
     /// Example
@@ -106,29 +123,36 @@ impl<'a, T: 'a + ?Sized> Rob<'a, T> {
             marker2: PhantomData,
         }
     }
-    
+
     /// Creates a new `Rob` from a raw pointer and an owned flag. If
     /// `is_owned` is `true`, `ptr` should come from `Box::into_raw`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be non-null and valid for the lifetime `'a`. If
+    /// `is_owned` is `true`, `ptr` must have been obtained from
+    /// `Box::into_raw` so it can be freed on drop; if it is `false`,
+    /// it must come from a borrow that outlives `'a`.
     #[inline]
     pub const unsafe fn from_raw(ptr: *mut T, is_owned: bool) -> Self {
         Self {
             ptr: NonNull::new_unchecked(ptr),
-            is_owned: is_owned,
+            is_owned,
             marker1: PhantomData,
             marker2: PhantomData,
         }
     }
-    
+
     /// Consumes `this`, returning a raw pointer to the value and a
     /// flag indicating whether the values is owned or not.
     #[inline]
     pub fn into_raw(this: Self) -> (*mut T, bool) {
         let ptr = this.ptr.as_ptr();
         let is_owned = this.is_owned;
-        std::mem::forget(this);
+        core::mem::forget(this);
         (ptr, is_owned)
     }
-    
+
     /// If the value is not owned, returns a reference to it with
     /// lifetime `'a`.
     #[inline]
@@ -139,30 +163,58 @@ impl<'a, T: 'a + ?Sized> Rob<'a, T> {
             None
         }
     }
-    
+
     /// Returns whether the value is owned or not.
     #[inline]
     pub const fn is_owned(this: &Self) -> bool {
         this.is_owned
     }
+
+    /// If the value is owned, consumes `this` and returns the inner
+    /// `Box`. Otherwise returns `this` back unchanged, never cloning.
+    ///
+    /// Unlike `into_box`, this does not require `T: ToOwned`, so it
+    /// works with payloads that are neither `Clone` nor `ToOwned`.
+    #[inline]
+    pub fn try_into_box(this: Self) -> Result<Box<T>, Self> {
+        if this.is_owned {
+            let ptr = this.ptr.as_ptr();
+            core::mem::forget(this);
+            Ok(unsafe { Box::from_raw(ptr) })
+        } else {
+            Err(this)
+        }
+    }
+
+    /// If the value is owned, returns a mutable reference to it;
+    /// otherwise returns `None`. Unlike `to_mut`, this never promotes a
+    /// borrow to an owned value, so it requires no `ToOwned` bound.
+    #[inline]
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        if this.is_owned {
+            unsafe { Some(&mut *this.ptr.as_ptr()) }
+        } else {
+            None
+        }
+    }
 }
 
 impl<'a, T: 'a + ?Sized> Rob<'a, T>
-    where T: std::borrow::ToOwned,
-          <T as std::borrow::ToOwned>::Owned: Into<Box<T>>
+    where T: alloc::borrow::ToOwned,
+          <T as alloc::borrow::ToOwned>::Owned: Into<Box<T>>
 {
     /// Consumes `this`, returning a `Box` containing the value, cloning
     /// it if it was not owned.
     pub fn into_box(this: Self) -> Box<T> {
         if this.is_owned {
             let ptr = this.ptr.as_ptr();
-            std::mem::forget(this);
+            core::mem::forget(this);
             unsafe { Box::from_raw(ptr) }
         } else {
             this.to_owned().into()
         }
     }
-    
+
     /// Returns a mutable reference to the value, cloning it if it was
     /// not owned.
     pub fn to_mut(this: &mut Self) -> &mut T {
@@ -172,10 +224,34 @@ impl<'a, T: 'a + ?Sized> Rob<'a, T>
                 this.ptr = NonNull::new_unchecked(Box::into_raw(b));
                 this.is_owned = true;
             }
-            
+
             &mut *this.ptr.as_mut()
         }
     }
+
+    /// Consumes `this`, detaching it from `'a` by cloning a borrowed
+    /// value into an owned `Box` so the result can outlive `'a`. An
+    /// already owned value is reused without cloning.
+    pub fn into_owned(this: Self) -> Rob<'static, T> {
+        if this.is_owned {
+            let ptr = this.ptr.as_ptr();
+            core::mem::forget(this);
+            unsafe { Rob::from_raw(ptr, true) }
+        } else {
+            Rob::from_box(this.to_owned().into())
+        }
+    }
+}
+
+impl<'a, T: 'a + ?Sized> Rob<'a, T>
+    where T: alloc::borrow::ToOwned
+{
+    /// Consumes `this`, returning a `Cow` that borrows when `this` was
+    /// borrowed and owns (via `ToOwned`) when it was owned.
+    #[inline]
+    pub fn into_cow(this: Self) -> alloc::borrow::Cow<'a, T> {
+        this.into()
+    }
 }
 
 impl<'a, T: 'a> From<T> for Rob<'a, T> {
@@ -199,20 +275,21 @@ impl<'a, T: 'a + ?Sized> From<Box<T>> for Rob<'a, T> {
     }
 }
 
-impl<'a, T: 'a> From<Vec<T>> for Rob<'a, [T]> {
+impl<'a, T: 'a> From<alloc::vec::Vec<T>> for Rob<'a, [T]> {
     #[inline]
-    fn from(vec: Vec<T>) -> Self {
+    fn from(vec: alloc::vec::Vec<T>) -> Self {
         Self::from_box(vec.into_boxed_slice())
     }
 }
 
-impl<'a> From<String> for Rob<'a, str> {
+impl<'a> From<alloc::string::String> for Rob<'a, str> {
     #[inline]
-    fn from(s: String) -> Self {
+    fn from(s: alloc::string::String) -> Self {
         Self::from_box(s.into_boxed_str())
     }
 }
 
+#[cfg(feature = "std")]
 impl<'a> From<std::ffi::CString> for Rob<'a, std::ffi::CStr> {
     #[inline]
     fn from(s: std::ffi::CString) -> Self {
@@ -220,6 +297,7 @@ impl<'a> From<std::ffi::CString> for Rob<'a, std::ffi::CStr> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<'a> From<std::ffi::OsString> for Rob<'a, std::ffi::OsStr> {
     #[inline]
     fn from(s: std::ffi::OsString) -> Self {
@@ -227,6 +305,7 @@ impl<'a> From<std::ffi::OsString> for Rob<'a, std::ffi::OsStr> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<'a> From<std::path::PathBuf> for Rob<'a, std::path::Path> {
     #[inline]
     fn from(s: std::path::PathBuf) -> Self {
@@ -234,14 +313,25 @@ impl<'a> From<std::path::PathBuf> for Rob<'a, std::path::Path> {
     }
 }
 
-impl<'a, T> From<std::borrow::Cow<'a, T>> for Rob<'a, T>
-    where T: std::borrow::ToOwned,
-          <T as std::borrow::ToOwned>::Owned: Into<Box<T>>,
+impl<'a, T> From<alloc::borrow::Cow<'a, T>> for Rob<'a, T>
+    where T: alloc::borrow::ToOwned,
+          <T as alloc::borrow::ToOwned>::Owned: Into<Box<T>>,
 {
-    fn from(cow: std::borrow::Cow<'a, T>) -> Self {
+    fn from(cow: alloc::borrow::Cow<'a, T>) -> Self {
         match cow {
-            std::borrow::Cow::Borrowed(r) => Self::from_ref(r),
-            std::borrow::Cow::Owned(o) => Self::from_box(o.into()),
+            alloc::borrow::Cow::Borrowed(r) => Self::from_ref(r),
+            alloc::borrow::Cow::Owned(o) => Self::from_box(o.into()),
+        }
+    }
+}
+
+impl<'a, T: 'a + ?Sized> From<Rob<'a, T>> for alloc::borrow::Cow<'a, T>
+    where T: alloc::borrow::ToOwned,
+{
+    fn from(this: Rob<'a, T>) -> Self {
+        match Rob::as_ref(&this) {
+            Some(r) => alloc::borrow::Cow::Borrowed(r),
+            None => alloc::borrow::Cow::Owned(this.to_owned()),
         }
     }
 }
@@ -259,7 +349,7 @@ impl<'a, T: 'a + Clone> Clone for Rob<'a, T> {
 }
 
 impl<'a, T: 'a + ?Sized + Debug> Debug for Rob<'a, T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         <T as Debug>::fmt(&**self, f)
     }
 }
@@ -275,59 +365,304 @@ impl<'a, T: 'a + ?Sized + PartialEq> PartialEq for Rob<'a, T> {
     fn eq(&self, other: &Rob<'a, T>) -> bool {
         <T as PartialEq>::eq(&**self, &**other)
     }
-    
-    #[inline]
-    fn ne(&self, other: &Rob<'a, T>) -> bool {
-        <T as PartialEq>::ne(&**self, &**other)
-    }
 }
 
 impl<'a, T: 'a + ?Sized + PartialOrd> PartialOrd for Rob<'a, T> {
     #[inline]
-    fn partial_cmp(&self, other: &Rob<'a, T>) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Rob<'a, T>) -> Option<core::cmp::Ordering> {
         <T as PartialOrd>::partial_cmp(&**self, &**other)
     }
-    
+
     #[inline]
     fn lt(&self, other: &Rob<'a, T>) -> bool {
         <T as PartialOrd>::lt(&**self, &**other)
     }
-    
+
     #[inline]
     fn le(&self, other: &Rob<'a, T>) -> bool {
         <T as PartialOrd>::le(&**self, &**other)
     }
-    
+
     #[inline]
     fn ge(&self, other: &Rob<'a, T>) -> bool {
         <T as PartialOrd>::ge(&**self, &**other)
     }
-    
+
     #[inline]
     fn gt(&self, other: &Rob<'a, T>) -> bool {
         <T as PartialOrd>::gt(&**self, &**other)
     }
 }
 
-impl<'a, T: 'a + ?Sized> std::ops::Deref for Rob<'a, T> {
+impl<'a, T: 'a + ?Sized> core::ops::Deref for Rob<'a, T> {
     type Target = T;
-    
+
     #[inline]
     fn deref(&self) -> &T {
         unsafe { &*self.ptr.as_ptr() }
     }
 }
 
-impl<'a, T: 'a + ?Sized> std::borrow::Borrow<T> for Rob<'a, T> {
+impl<'a, T: 'a + ?Sized> core::borrow::Borrow<T> for Rob<'a, T> {
     #[inline]
     fn borrow(&self) -> &T {
-        &**self
+        self
     }
 }
 
 impl<'a, T: 'a + ?Sized> AsRef<T> for Rob<'a, T> {
     #[inline]
     fn as_ref(&self) -> &T {
-        &**self
+        self
+    }
+}
+
+impl<'a> Rob<'a, str> {
+    /// Appends `other` to the string, promoting a borrowed value to an
+    /// owned `Box<str>` and reusing the existing allocation when already
+    /// owned. Leaves `self` in the owned state.
+    fn append(&mut self, other: &str) {
+        let cur = core::mem::replace(self, Self::from_ref(""));
+        let mut s = if Self::is_owned(&cur) {
+            alloc::string::String::from(Self::into_box(cur))
+        } else {
+            let mut s = alloc::string::String::with_capacity(cur.len() + other.len());
+            s.push_str(&cur);
+            s
+        };
+        s.push_str(other);
+        *self = Self::from_box(s.into_boxed_str());
+    }
+}
+
+impl<'a, 'b> core::ops::AddAssign<&'b str> for Rob<'a, str> {
+    #[inline]
+    fn add_assign(&mut self, other: &'b str) {
+        self.append(other);
+    }
+}
+
+impl<'a> core::ops::AddAssign<Rob<'a, str>> for Rob<'a, str> {
+    #[inline]
+    fn add_assign(&mut self, other: Rob<'a, str>) {
+        self.append(&other);
+    }
+}
+
+impl<'a> core::ops::AddAssign<alloc::borrow::Cow<'a, str>> for Rob<'a, str> {
+    #[inline]
+    fn add_assign(&mut self, other: alloc::borrow::Cow<'a, str>) {
+        self.append(&other);
+    }
+}
+
+impl<'a, 'b> core::ops::Add<&'b str> for Rob<'a, str> {
+    type Output = Rob<'a, str>;
+
+    #[inline]
+    fn add(mut self, other: &'b str) -> Self {
+        self.append(other);
+        self
+    }
+}
+
+impl<'a> core::ops::Add<Rob<'a, str>> for Rob<'a, str> {
+    type Output = Rob<'a, str>;
+
+    #[inline]
+    fn add(mut self, other: Rob<'a, str>) -> Self {
+        self.append(&other);
+        self
+    }
+}
+
+impl<'a> core::ops::Add<alloc::borrow::Cow<'a, str>> for Rob<'a, str> {
+    type Output = Rob<'a, str>;
+
+    #[inline]
+    fn add(mut self, other: alloc::borrow::Cow<'a, str>) -> Self {
+        self.append(&other);
+        self
+    }
+}
+
+/// The mutable counterpart of `Rob`. It can contain either a unique
+/// borrow (`&'a mut T`) or an owned `Box`, in the same pointer and flag
+/// layout as `Rob`. Because a unique borrow can be mutated in place,
+/// `RobMut` implements `DerefMut` and needs no clone-on-write step for
+/// mutation; only `into_box` clones, and only when the value is borrowed.
+pub struct RobMut<'a, T: 'a + ?Sized> {
+    ptr: NonNull<T>,
+    is_owned: bool,
+    marker1: PhantomData<&'a mut T>,
+    marker2: PhantomData<T>,
+}
+
+unsafe impl<'a, T: 'a + ?Sized> Send for RobMut<'a, T>
+where
+    T: Send
+{}
+
+unsafe impl<'a, T: 'a + ?Sized> Sync for RobMut<'a, T>
+where
+    T: Sync
+{}
+
+impl<'a, T: 'a + ?Sized> Drop for RobMut<'a, T> {
+    fn drop(&mut self) {
+        if self.is_owned {
+            let _ = unsafe { Box::from_raw(self.ptr.as_ptr()) };
+        }
+    }
+}
+
+impl<'a, T: 'a> RobMut<'a, T> {
+    /// Creates a new `RobMut` with an owned value.
+    ///
+    /// Example
+    /// -------
+    /// ```
+    /// let mut x = rob::RobMut::from_value(123i32);
+    /// *x += 1;
+    /// assert_eq!(*x, 124);
+    /// assert!(rob::RobMut::is_owned(&x));
+    /// ```
+    #[inline]
+    pub fn from_value(value: T) -> Self {
+        Self::from_box(Box::new(value))
+    }
+}
+
+impl<'a, T: 'a + ?Sized> RobMut<'a, T> {
+    /// Creates a new `RobMut` with a unique borrow.
+    ///
+    /// Example
+    /// -------
+    /// ```
+    /// let mut value = 123i32;
+    /// let mut x = rob::RobMut::from_mut(&mut value);
+    /// *x += 1;
+    /// assert_eq!(*x, 124);
+    /// assert!(!rob::RobMut::is_owned(&x));
+    /// ```
+    #[inline]
+    pub fn from_mut(r: &'a mut T) -> Self {
+        Self {
+            ptr: NonNull::from(r),
+            is_owned: false,
+            marker1: PhantomData,
+            marker2: PhantomData,
+        }
+    }
+
+    /// Creates a new `RobMut` with an owned value that is already boxed.
+    #[inline]
+    pub fn from_box(b: Box<T>) -> Self {
+        Self {
+            ptr: unsafe { NonNull::new_unchecked(Box::into_raw(b)) },
+            is_owned: true,
+            marker1: PhantomData,
+            marker2: PhantomData,
+        }
+    }
+
+    /// Creates a new `RobMut` from a raw pointer and an owned flag. If
+    /// `is_owned` is `true`, `ptr` should come from `Box::into_raw`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be non-null and valid for the lifetime `'a`. If
+    /// `is_owned` is `true`, `ptr` must have been obtained from
+    /// `Box::into_raw` so it can be freed on drop; if it is `false`,
+    /// it must come from a unique borrow that outlives `'a`.
+    #[inline]
+    pub const unsafe fn from_raw(ptr: *mut T, is_owned: bool) -> Self {
+        Self {
+            ptr: NonNull::new_unchecked(ptr),
+            is_owned,
+            marker1: PhantomData,
+            marker2: PhantomData,
+        }
+    }
+
+    /// Consumes `this`, returning a raw pointer to the value and a
+    /// flag indicating whether the values is owned or not.
+    #[inline]
+    pub fn into_raw(this: Self) -> (*mut T, bool) {
+        let ptr = this.ptr.as_ptr();
+        let is_owned = this.is_owned;
+        core::mem::forget(this);
+        (ptr, is_owned)
+    }
+
+    /// Returns whether the value is owned or not.
+    #[inline]
+    pub const fn is_owned(this: &Self) -> bool {
+        this.is_owned
+    }
+}
+
+impl<'a, T: 'a + ?Sized> RobMut<'a, T>
+    where T: alloc::borrow::ToOwned,
+          <T as alloc::borrow::ToOwned>::Owned: Into<Box<T>>
+{
+    /// Consumes `this`, returning a `Box` containing the value. When the
+    /// value is owned the box is moved out without cloning; when it was
+    /// only a borrow it is cloned via `ToOwned`.
+    pub fn into_box(this: Self) -> Box<T> {
+        if this.is_owned {
+            let ptr = this.ptr.as_ptr();
+            core::mem::forget(this);
+            unsafe { Box::from_raw(ptr) }
+        } else {
+            this.to_owned().into()
+        }
+    }
+}
+
+impl<'a, T: 'a + ?Sized> core::ops::Deref for RobMut<'a, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { &*self.ptr.as_ptr() }
+    }
+}
+
+impl<'a, T: 'a + ?Sized> core::ops::DerefMut for RobMut<'a, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.ptr.as_ptr() }
+    }
+}
+
+impl<'a, T: 'a> From<T> for RobMut<'a, T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Self::from_value(value)
+    }
+}
+
+impl<'a, T: 'a + ?Sized> From<&'a mut T> for RobMut<'a, T> {
+    #[inline]
+    fn from(r: &'a mut T) -> Self {
+        Self::from_mut(r)
+    }
+}
+
+impl<'a, T: 'a + ?Sized> From<Box<T>> for RobMut<'a, T> {
+    #[inline]
+    fn from(b: Box<T>) -> Self {
+        Self::from_box(b)
+    }
+}
+
+/// Downgrades a `RobMut` into a `Rob`, turning a unique borrow into a
+/// shared one and keeping ownership of an owned box.
+impl<'a, T: 'a + ?Sized> From<RobMut<'a, T>> for Rob<'a, T> {
+    #[inline]
+    fn from(this: RobMut<'a, T>) -> Self {
+        let (ptr, is_owned) = RobMut::into_raw(this);
+        unsafe { Rob::from_raw(ptr, is_owned) }
     }
 }
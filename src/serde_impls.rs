@@ -0,0 +1,60 @@
+// Copyright 2018 Eduardo Sánchez Muñoz
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! `serde` `Serialize`/`Deserialize` implementations, gated on the
+//! `serde` feature.
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use serde::de::{Deserialize, DeserializeOwned, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
+use Rob;
+
+// A borrowed and an owned `Rob` serialize identically because the value
+// is always reached through the `Deref`.
+impl<'a, T: 'a + ?Sized + Serialize> Serialize for Rob<'a, T> {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        <T as Serialize>::serialize(&**self, serializer)
+    }
+}
+
+// Deserialization cannot borrow from the `Rob`'s own lifetime, so it
+// always produces the owned variant.
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Rob<'static, T> {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        Ok(Self::from_box(Box::new(T::deserialize(deserializer)?)))
+    }
+}
+
+impl<'de> Deserialize<'de> for Rob<'static, str> {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        let s = String::deserialize(deserializer)?;
+        Ok(Self::from_box(s.into_boxed_str()))
+    }
+}
+
+impl<'de, U: DeserializeOwned> Deserialize<'de> for Rob<'static, [U]> {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where D: Deserializer<'de>
+    {
+        let v = Vec::<U>::deserialize(deserializer)?;
+        Ok(Self::from_box(v.into_boxed_slice()))
+    }
+}
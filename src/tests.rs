@@ -8,11 +8,15 @@
 use std;
 use std::cell::Cell;
 
+use alloc::boxed::Box;
+use alloc::string::String;
+
 use Rob;
+use RobMut;
 
 // tests might run in multiple threads
-thread_local! {
-    static DROP_COUNT: Cell<usize> = Cell::new(0);
+std::thread_local! {
+    static DROP_COUNT: Cell<usize> = const { Cell::new(0) };
 }
 
 #[derive(Clone)]
@@ -83,3 +87,118 @@ fn test_borrowed_into_box() {
     std::mem::drop(obj);
     assert_eq!(DROP_COUNT.with(|cnt| cnt.get()), 2);
 }
+
+#[test]
+fn test_robmut_owned_drops_once() {
+    DROP_COUNT.with(|cnt| cnt.set(0));
+    let x = RobMut::from_value(TestObj(123));
+    assert!(RobMut::is_owned(&x));
+    assert_eq!(x.0, 123);
+    std::mem::drop(x);
+    assert_eq!(DROP_COUNT.with(|cnt| cnt.get()), 1);
+}
+
+#[test]
+fn test_robmut_borrowed_does_not_drop() {
+    DROP_COUNT.with(|cnt| cnt.set(0));
+    let mut obj = TestObj(123);
+    {
+        let mut x = RobMut::from_mut(&mut obj);
+        assert!(!RobMut::is_owned(&x));
+        x.0 = 456;
+        std::mem::drop(x);
+        assert_eq!(DROP_COUNT.with(|cnt| cnt.get()), 0);
+    }
+    assert_eq!(obj.0, 456);
+    std::mem::drop(obj);
+    assert_eq!(DROP_COUNT.with(|cnt| cnt.get()), 1);
+}
+
+#[test]
+fn test_robmut_owned_into_box() {
+    DROP_COUNT.with(|cnt| cnt.set(0));
+    let x = RobMut::from_value(TestObj(123));
+    let b = RobMut::into_box(x);
+    assert_eq!(b.0, 123);
+    std::mem::drop(b);
+    assert_eq!(DROP_COUNT.with(|cnt| cnt.get()), 1);
+}
+
+#[test]
+fn test_robmut_borrowed_into_box_clones() {
+    DROP_COUNT.with(|cnt| cnt.set(0));
+    let mut obj = TestObj(123);
+    {
+        let x = RobMut::from_mut(&mut obj);
+        let b = RobMut::into_box(x);
+        assert_eq!(b.0, 123);
+        std::mem::drop(b);
+        assert_eq!(DROP_COUNT.with(|cnt| cnt.get()), 1);
+    }
+    std::mem::drop(obj);
+    assert_eq!(DROP_COUNT.with(|cnt| cnt.get()), 2);
+}
+
+#[test]
+fn test_robmut_downgrade_transfers_ownership() {
+    DROP_COUNT.with(|cnt| cnt.set(0));
+    let m = RobMut::from_value(TestObj(123));
+    let r: Rob<TestObj> = Rob::from(m);
+    assert!(Rob::is_owned(&r));
+    assert_eq!(r.0, 123);
+    std::mem::drop(r);
+    assert_eq!(DROP_COUNT.with(|cnt| cnt.get()), 1);
+}
+
+#[test]
+fn test_add_promotes_borrowed() {
+    let x = Rob::from_ref("a") + "b";
+    assert!(Rob::is_owned(&x));
+    assert_eq!(&*x, "ab");
+}
+
+#[test]
+fn test_add_reuses_owned() {
+    let owned = Rob::from_box(String::from("a").into_boxed_str());
+    let x = owned + "b";
+    assert!(Rob::is_owned(&x));
+    assert_eq!(&*x, "ab");
+}
+
+#[test]
+fn test_add_assign_operands() {
+    let mut x = Rob::from_ref("a");
+    x += "b";
+    x += Rob::from_ref("c");
+    x += alloc::borrow::Cow::Borrowed("d");
+    assert!(Rob::is_owned(&x));
+    assert_eq!(&*x, "abcd");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_round_trip() {
+    // A borrowed and an owned `Rob` must serialize identically, since
+    // the value is always reached through the `Deref`.
+    let text = String::from("hello");
+    let borrowed = Rob::from_ref(text.as_str());
+    let owned = Rob::from_box(text.clone().into_boxed_str());
+    let ser_borrowed = serde_json::to_string(&borrowed).unwrap();
+    let ser_owned = serde_json::to_string(&owned).unwrap();
+    assert_eq!(ser_borrowed, "\"hello\"");
+    assert_eq!(ser_borrowed, ser_owned);
+
+    // Deserialization always yields the owned variant.
+    let de: Rob<str> = serde_json::from_str("\"hello\"").unwrap();
+    assert!(Rob::is_owned(&de));
+    assert_eq!(&*de, "hello");
+
+    // The sized and slice cases go through the same `from_box` path.
+    let de_int: Rob<i32> = serde_json::from_str("123").unwrap();
+    assert!(Rob::is_owned(&de_int));
+    assert_eq!(*de_int, 123);
+
+    let de_slice: Rob<[i32]> = serde_json::from_str("[1,2,3]").unwrap();
+    assert!(Rob::is_owned(&de_slice));
+    assert_eq!(&*de_slice, &[1, 2, 3]);
+}